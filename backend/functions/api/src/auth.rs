@@ -0,0 +1,36 @@
+//! Bearer/cookie JWT extraction for protected routes
+
+use lambda_http::Request;
+use once_cell::sync::OnceCell;
+use shared::auth::{verify_token, Claims};
+use shared::AppError;
+
+use crate::cookies;
+
+/// JWT signing secret, read from the environment once at startup
+pub static JWT_SECRET: OnceCell<Vec<u8>> = OnceCell::new();
+
+const AUTH_COOKIE_NAME: &str = "btlrun_session";
+
+/// Extracts a bearer token or session cookie from the request and verifies it
+pub fn authenticate(event: &Request) -> Result<Claims, AppError> {
+    let secret = JWT_SECRET
+        .get()
+        .ok_or_else(|| AppError::Internal("JWT secret not configured".to_string()))?;
+
+    let token = bearer_token(event)
+        .or_else(|| cookies::get(event, AUTH_COOKIE_NAME))
+        .ok_or(AppError::Unauthorized)?;
+
+    verify_token(&token, secret)
+}
+
+fn bearer_token(event: &Request) -> Option<String> {
+    event
+        .headers()
+        .get("authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|token| token.to_string())
+}
@@ -0,0 +1,87 @@
+//! Transparent gzip compression for outgoing responses
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lambda_http::{Body, Error, Response};
+use std::io::Write;
+
+/// Minimum body size, in bytes, before we bother gzip-compressing a response
+const MIN_COMPRESSIBLE_SIZE: usize = 1024;
+
+/// Gzip-compresses `response`'s body when `accept_encoding` advertises gzip support and the
+/// body is large enough to be worth compressing; otherwise returns the response unchanged.
+pub fn compress_if_supported(
+    accept_encoding: Option<&str>,
+    response: Response<Body>,
+) -> Result<Response<Body>, Error> {
+    let supports_gzip = accept_encoding.is_some_and(|value| value.contains("gzip"));
+    let body: &[u8] = response.body().as_ref();
+
+    if !supports_gzip || body.len() < MIN_COMPRESSIBLE_SIZE {
+        return Ok(response);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body)?;
+    let compressed = encoder.finish()?;
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.insert("content-encoding", "gzip".parse()?);
+    Ok(Response::from_parts(parts, Body::from(compressed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn response_with_body(len: usize) -> Response<Body> {
+        Response::builder()
+            .status(200)
+            .body(Body::from(vec![b'a'; len]))
+            .expect("response is well-formed")
+    }
+
+    #[test]
+    fn compresses_bodies_at_or_above_the_threshold_when_gzip_is_supported() {
+        let body = vec![b'a'; MIN_COMPRESSIBLE_SIZE];
+        let response = compress_if_supported(Some("gzip, deflate"), response_with_body(MIN_COMPRESSIBLE_SIZE))
+            .expect("compression does not fail");
+
+        assert_eq!(response.headers().get("content-encoding").unwrap(), "gzip");
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(response.body().as_ref())
+            .read_to_end(&mut decoded)
+            .expect("compressed body decodes as gzip");
+        assert_eq!(decoded, body);
+    }
+
+    #[test]
+    fn leaves_small_bodies_uncompressed_even_when_gzip_is_supported() {
+        let response = compress_if_supported(
+            Some("gzip"),
+            response_with_body(MIN_COMPRESSIBLE_SIZE - 1),
+        )
+        .expect("compression does not fail");
+
+        assert!(response.headers().get("content-encoding").is_none());
+    }
+
+    #[test]
+    fn leaves_large_bodies_uncompressed_when_the_client_does_not_support_gzip() {
+        let response = compress_if_supported(Some("br"), response_with_body(MIN_COMPRESSIBLE_SIZE))
+            .expect("compression does not fail");
+
+        assert!(response.headers().get("content-encoding").is_none());
+    }
+
+    #[test]
+    fn leaves_large_bodies_uncompressed_without_an_accept_encoding_header() {
+        let response = compress_if_supported(None, response_with_body(MIN_COMPRESSIBLE_SIZE))
+            .expect("compression does not fail");
+
+        assert!(response.headers().get("content-encoding").is_none());
+    }
+}
@@ -0,0 +1,13 @@
+//! Helper for reading a single cookie value out of a request's `Cookie` header
+
+use lambda_http::Request;
+
+/// Returns the value of the named cookie, if present
+pub fn get(event: &Request, name: &str) -> Option<String> {
+    let cookie_header = event.headers().get("cookie")?.to_str().ok()?;
+
+    cookie_header.split(';').map(|pair| pair.trim()).find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then(|| value.to_string())
+    })
+}
@@ -0,0 +1,108 @@
+//! Double-submit-cookie CSRF enforcement wired into request dispatch
+
+use lambda_http::Request;
+use shared::csrf::{generate_csrf_token, verify_csrf};
+use shared::AppError;
+
+use crate::cookies;
+
+const CSRF_COOKIE_NAME: &str = "btlrun_csrf";
+const CSRF_HEADER_NAME: &str = "x-csrf-token";
+
+/// Builds the `Set-Cookie` header value for a freshly generated CSRF token
+fn issue_cookie_header() -> String {
+    format!(
+        "{CSRF_COOKIE_NAME}={}; SameSite=Strict; Path=/; Secure",
+        generate_csrf_token()
+    )
+}
+
+/// Builds a `Set-Cookie` header for a fresh CSRF token, but only if `event` doesn't already
+/// carry one — rotating the cookie on every `GET` would invalidate a token a client already
+/// has in hand (e.g. mid-way through filling out a form) and cause spurious CSRF failures.
+pub fn issue_cookie_header_if_absent(event: &Request) -> Option<String> {
+    if cookies::get(event, CSRF_COOKIE_NAME).is_some() {
+        None
+    } else {
+        Some(issue_cookie_header())
+    }
+}
+
+/// Verifies that an unsafe-method request echoes its CSRF cookie in the `X-CSRF-Token` header.
+///
+/// Called generically in `handler()` for every `POST`/`PUT`/`DELETE` whose path resolves to a
+/// known route, before dispatch, so mutating endpoints are protected by default and requests to
+/// nonexistent routes still 404 instead of failing CSRF first.
+pub fn verify_request(event: &Request) -> Result<(), AppError> {
+    let cookie = cookies::get(event, CSRF_COOKIE_NAME)
+        .ok_or_else(|| AppError::BadRequest("missing CSRF cookie".to_string()))?;
+
+    let header = event
+        .headers()
+        .get(CSRF_HEADER_NAME)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::BadRequest("missing X-CSRF-Token header".to_string()))?;
+
+    if verify_csrf(&cookie, header) {
+        Ok(())
+    } else {
+        Err(AppError::BadRequest("CSRF token mismatch".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lambda_http::Body;
+
+    fn request_with(cookie: Option<&str>, header: Option<&str>) -> Request {
+        let mut builder = lambda_http::http::Request::builder()
+            .method("POST")
+            .uri("/api/example");
+
+        if let Some(cookie) = cookie {
+            builder = builder.header("cookie", format!("{CSRF_COOKIE_NAME}={cookie}"));
+        }
+        if let Some(header) = header {
+            builder = builder.header(CSRF_HEADER_NAME, header);
+        }
+
+        builder.body(Body::Empty).expect("request is well-formed")
+    }
+
+    #[test]
+    fn verify_request_accepts_a_matching_cookie_and_header() {
+        let event = request_with(Some("matching-token"), Some("matching-token"));
+        assert!(verify_request(&event).is_ok());
+    }
+
+    #[test]
+    fn verify_request_rejects_a_mismatched_header() {
+        let event = request_with(Some("cookie-token"), Some("different-token"));
+        assert!(matches!(verify_request(&event), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn verify_request_rejects_a_missing_cookie() {
+        let event = request_with(None, Some("header-token"));
+        assert!(matches!(verify_request(&event), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn verify_request_rejects_a_missing_header() {
+        let event = request_with(Some("cookie-token"), None);
+        assert!(matches!(verify_request(&event), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn issue_cookie_header_if_absent_skips_requests_that_already_have_one() {
+        let event = request_with(Some("existing-token"), None);
+        assert!(issue_cookie_header_if_absent(&event).is_none());
+    }
+
+    #[test]
+    fn issue_cookie_header_if_absent_issues_one_for_a_fresh_request() {
+        let event = request_with(None, None);
+        assert!(issue_cookie_header_if_absent(&event).is_some());
+    }
+}
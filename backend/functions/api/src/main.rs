@@ -4,56 +4,212 @@
 //! Handles routing and request processing.
 
 use lambda_http::{run, service_fn, Body, Error, Request, Response};
-use shared::{ApiResponse, HealthResponse};
+use shared::{cors_headers, AppError, ApiResponse, HealthResponse};
+use std::time::Instant;
 use tracing::info;
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use utoipa::OpenApi;
+
+mod auth;
+mod compression;
+mod cookies;
+mod csrf;
+mod metrics;
+mod openapi;
+
+use openapi::ApiDoc;
 
 /// Main request handler
 async fn handler(event: Request) -> Result<Response<Body>, Error> {
-    let path = event.uri().path();
-    let method = event.method().as_str();
+    let path = event.uri().path().to_string();
+    let method = event.method().as_str().to_string();
+    let accept_encoding = event
+        .headers()
+        .get("accept-encoding")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
 
     info!(path = %path, method = %method, "Handling request");
 
-    match (method, path) {
-        ("GET", "/health") | ("GET", "/api/health") => health_handler().await,
-        ("GET", "/") | ("GET", "/api") => root_handler().await,
-        _ => not_found_handler(path).await,
+    let route = matched_route(&method, &path);
+    metrics::REQUESTS_TOTAL.with_label_values(&[&method, route]).inc();
+    let timer = Instant::now();
+
+    // CSRF is enforced generically for mutating methods, but only once the path is known to
+    // resolve to a route, so a POST/PUT/DELETE to a nonexistent path still 404s instead of
+    // failing CSRF first.
+    let csrf_check = if matches!(method.as_str(), "POST" | "PUT" | "DELETE") && known_path(&path) {
+        csrf::verify_request(&event)
+    } else {
+        Ok(())
+    };
+
+    let result = match csrf_check {
+        Err(err) => Err(err),
+        Ok(()) => match (method.as_str(), path.as_str()) {
+            ("GET", "/health") | ("GET", "/api/health") => health_handler().await,
+            ("GET", "/") | ("GET", "/api") => root_handler().await,
+            ("GET", "/openapi.json") => openapi_handler().await,
+            ("GET", "/docs") => docs_handler().await,
+            ("GET", "/metrics") => metrics_handler().await,
+            ("GET", "/api/me") => me_handler(&event).await,
+            ("OPTIONS", _) => preflight_handler().await,
+            _ => Err(AppError::NotFound(path.clone())),
+        },
+    };
+
+    let response = match result {
+        Ok(response) => Ok(response),
+        Err(err) => err.into_response(),
+    };
+    let response = response.map(|mut r| {
+        if method == "GET" {
+            if let Some(cookie_header) = csrf::issue_cookie_header_if_absent(&event) {
+                if let Ok(value) = cookie_header.parse() {
+                    r.headers_mut().insert("set-cookie", value);
+                }
+            }
+        }
+        r
+    });
+    let response =
+        response.and_then(|r| compression::compress_if_supported(accept_encoding.as_deref(), r));
+
+    metrics::HANDLER_DURATION_SECONDS.observe(timer.elapsed().as_secs_f64());
+    if let Ok(response) = &response {
+        metrics::RESPONSES_TOTAL
+            .with_label_values(&[response.status().as_str()])
+            .inc();
     }
+
+    response
 }
 
 /// Health check endpoint
-async fn health_handler() -> Result<Response<Body>, Error> {
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Service is healthy", body = ApiResponseHealth)
+    )
+)]
+async fn health_handler() -> Result<Response<Body>, AppError> {
     let response = ApiResponse::success(HealthResponse::default());
     json_response(200, &response)
 }
 
 /// Root endpoint
-async fn root_handler() -> Result<Response<Body>, Error> {
+#[utoipa::path(
+    get,
+    path = "/",
+    responses(
+        (status = 200, description = "API metadata")
+    )
+)]
+async fn root_handler() -> Result<Response<Body>, AppError> {
     let response = ApiResponse::success(serde_json::json!({
         "name": "btl.run API",
         "version": env!("CARGO_PKG_VERSION"),
-        "endpoints": ["/health", "/api/health"]
+        "endpoints": ["/health", "/api/health", "/openapi.json", "/docs", "/metrics", "/api/me"]
     }));
     json_response(200, &response)
 }
 
-/// 404 handler
-async fn not_found_handler(path: &str) -> Result<Response<Body>, Error> {
-    let response: ApiResponse<()> = ApiResponse::error(format!("Not found: {}", path));
-    json_response(404, &response)
+/// Serves the generated OpenAPI document as JSON
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    responses(
+        (status = 200, description = "The generated OpenAPI document")
+    )
+)]
+async fn openapi_handler() -> Result<Response<Body>, AppError> {
+    json_response(200, &ApiDoc::openapi())
+}
+
+/// Serves a Swagger UI page that points at `/openapi.json`
+#[utoipa::path(
+    get,
+    path = "/docs",
+    responses(
+        (status = 200, description = "Swagger UI HTML page")
+    )
+)]
+async fn docs_handler() -> Result<Response<Body>, AppError> {
+    cors_headers(Response::builder().status(200))
+        .header("content-type", "text/html; charset=utf-8")
+        .body(Body::from(openapi::swagger_ui_html()))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Returns the subject of the caller's verified JWT
+#[utoipa::path(
+    get,
+    path = "/api/me",
+    responses(
+        (status = 200, description = "Decoded subject of the caller's JWT"),
+        (status = 401, description = "Missing or invalid token")
+    )
+)]
+async fn me_handler(event: &Request) -> Result<Response<Body>, AppError> {
+    let claims = auth::authenticate(event)?;
+    let response = ApiResponse::success(serde_json::json!({ "sub": claims.sub }));
+    json_response(200, &response)
+}
+
+/// Responds to CORS preflight requests with the allowed methods/headers
+async fn preflight_handler() -> Result<Response<Body>, AppError> {
+    cors_headers(Response::builder().status(204))
+        .body(Body::default())
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Exposes gathered metrics in Prometheus text exposition format
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Prometheus metrics in text exposition format")
+    )
+)]
+async fn metrics_handler() -> Result<Response<Body>, AppError> {
+    cors_headers(Response::builder().status(200))
+        .header("content-type", "text/plain; version=0.0.4")
+        .body(Body::from(metrics::gather()))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Reports whether `path` resolves to a route, independent of method — used to gate CSRF
+/// enforcement so a mutating request to a nonexistent path 404s instead of failing CSRF first
+fn known_path(path: &str) -> bool {
+    matches!(
+        path,
+        "/health" | "/api/health" | "/" | "/api" | "/openapi.json" | "/docs" | "/metrics" | "/api/me"
+    )
+}
+
+/// Maps a request to a fixed, low-cardinality route label for metrics, instead of the raw
+/// (potentially attacker-controlled) request path
+fn matched_route<'a>(method: &str, path: &'a str) -> &'a str {
+    match (method, path) {
+        ("GET", "/health") | ("GET", "/api/health") => "/health",
+        ("GET", "/") | ("GET", "/api") => "/",
+        ("GET", "/openapi.json") => "/openapi.json",
+        ("GET", "/docs") => "/docs",
+        ("GET", "/metrics") => "/metrics",
+        ("GET", "/api/me") => "/api/me",
+        ("OPTIONS", _) => "options",
+        _ => "unmatched",
+    }
 }
 
 /// Helper to create JSON responses
-fn json_response<T: serde::Serialize>(status: u16, body: &T) -> Result<Response<Body>, Error> {
-    let json = serde_json::to_string(body)?;
-    Ok(Response::builder()
-        .status(status)
+fn json_response<T: serde::Serialize>(status: u16, body: &T) -> Result<Response<Body>, AppError> {
+    let json = serde_json::to_string(body).map_err(|e| AppError::Internal(e.to_string()))?;
+    cors_headers(Response::builder().status(status))
         .header("content-type", "application/json")
-        .header("access-control-allow-origin", "*")
-        .header("access-control-allow-methods", "GET, POST, PUT, DELETE, OPTIONS")
-        .header("access-control-allow-headers", "Content-Type, Authorization")
-        .body(Body::from(json))?)
+        .body(Body::from(json))
+        .map_err(|e| AppError::Internal(e.to_string()))
 }
 
 #[tokio::main]
@@ -69,7 +225,51 @@ async fn main() -> Result<(), Error> {
         .without_time()
         .init();
 
+    let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+    auth::JWT_SECRET
+        .set(jwt_secret.into_bytes())
+        .expect("JWT_SECRET is only set once, at startup");
+
     info!("Starting btl.run API Lambda");
 
     run(service_fn(handler)).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, uri: &str, cookie: Option<&str>, header: Option<&str>) -> Request {
+        let mut builder = lambda_http::http::Request::builder().method(method).uri(uri);
+
+        if let Some(cookie) = cookie {
+            builder = builder.header("cookie", format!("btlrun_csrf={cookie}"));
+        }
+        if let Some(header) = header {
+            builder = builder.header("x-csrf-token", header);
+        }
+
+        builder.body(Body::Empty).expect("request is well-formed")
+    }
+
+    #[tokio::test]
+    async fn post_to_a_known_route_without_a_csrf_token_is_rejected() {
+        let event = request("POST", "/api/me", None, None);
+        let response = handler(event).await.expect("handler does not error");
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn post_to_a_known_route_with_a_mismatched_csrf_token_is_rejected() {
+        let event = request("POST", "/api/me", Some("cookie-token"), Some("different-token"));
+        let response = handler(event).await.expect("handler does not error");
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn post_to_an_unknown_route_404s_instead_of_failing_csrf_first() {
+        let event = request("POST", "/no-such-route", None, None);
+        let response = handler(event).await.expect("handler does not error");
+        assert_eq!(response.status(), 404);
+    }
+}
@@ -0,0 +1,58 @@
+//! Prometheus metrics for the API Lambda
+//!
+//! Tracks request counts, response counts by status, and handler latency,
+//! and renders them in Prometheus text exposition format at `/metrics`.
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+pub static REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("btlrun_requests_total", "Total number of requests received"),
+        &["method", "path"],
+    )
+    .expect("metric names/labels are valid");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("metric is only registered once");
+    counter
+});
+
+pub static RESPONSES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("btlrun_responses_total", "Total number of responses sent, by status"),
+        &["status"],
+    )
+    .expect("metric names/labels are valid");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("metric is only registered once");
+    counter
+});
+
+pub static HANDLER_DURATION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    let histogram = Histogram::with_opts(HistogramOpts::new(
+        "btlrun_handler_duration_seconds",
+        "Time spent dispatching a request to its handler",
+    ))
+    .expect("histogram options are valid");
+    REGISTRY
+        .register(Box::new(histogram.clone()))
+        .expect("metric is only registered once");
+    histogram
+});
+
+/// Renders all registered metric families in Prometheus text exposition format
+pub fn gather() -> String {
+    let encoder = TextEncoder::new();
+    let metric_families = REGISTRY.gather();
+    let mut buf = Vec::new();
+    encoder
+        .encode(&metric_families, &mut buf)
+        .expect("encoding gathered metrics does not fail");
+    String::from_utf8(buf).expect("prometheus text encoding is valid utf-8")
+}
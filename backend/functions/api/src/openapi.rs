@@ -0,0 +1,50 @@
+//! OpenAPI document generation and Swagger UI for the API Lambda
+//!
+//! Collects the `utoipa::path` annotations on each handler into a single
+//! `#[derive(OpenApi)]` document, served at `/openapi.json`, with a minimal
+//! Swagger UI page at `/docs` that renders it.
+
+use shared::{AppError, ApiResponseHealth, HealthResponse};
+use utoipa::OpenApi;
+
+// `preflight_handler` is deliberately excluded: it answers CORS preflight for every path via
+// an `OPTIONS *` catch-all rather than a documented API operation, so it has no meaningful
+// single `path` to register here.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::health_handler,
+        crate::root_handler,
+        crate::me_handler,
+        crate::openapi_handler,
+        crate::docs_handler,
+        crate::metrics_handler,
+    ),
+    components(schemas(ApiResponseHealth, HealthResponse, AppError))
+)]
+pub struct ApiDoc;
+
+/// Minimal Swagger UI page that loads the spec from `/openapi.json`
+pub fn swagger_ui_html() -> String {
+    r##"<!DOCTYPE html>
+<html>
+  <head>
+    <title>btl.run API docs</title>
+    <meta charset="utf-8" />
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist/swagger-ui.css" />
+  </head>
+  <body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist/swagger-ui-bundle.js"></script>
+    <script>
+      window.onload = () => {
+        SwaggerUIBundle({
+          url: "/openapi.json",
+          dom_id: "#swagger-ui",
+        });
+      };
+    </script>
+  </body>
+</html>"##
+        .to_string()
+}
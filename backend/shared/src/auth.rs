@@ -0,0 +1,87 @@
+//! JWT authentication helpers shared across Lambda functions
+
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::AppError;
+
+/// Claims carried by btl.run access tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
+}
+
+/// Verify and decode a JWT, mapping any signature or expiry failure to `AppError::Unauthorized`
+pub fn verify_token(token: &str, secret: &[u8]) -> Result<Claims, AppError> {
+    let decoding_key = DecodingKey::from_secret(secret);
+    let validation = Validation::new(Algorithm::HS256);
+
+    decode::<Claims>(token, &decoding_key, &validation)
+        .map(|data| data.claims)
+        .map_err(|_| AppError::Unauthorized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unix_timestamp(offset_secs: i64) -> usize {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is after the epoch")
+            .as_secs() as i64;
+        (now + offset_secs) as usize
+    }
+
+    fn sign(claims: &Claims, secret: &[u8]) -> String {
+        encode(&Header::default(), claims, &EncodingKey::from_secret(secret))
+            .expect("encoding a well-formed token does not fail")
+    }
+
+    #[test]
+    fn verify_token_accepts_a_valid_token() {
+        let secret = b"test-secret";
+        let claims = Claims {
+            sub: "user-123".to_string(),
+            exp: unix_timestamp(3600),
+        };
+        let token = sign(&claims, secret);
+
+        let decoded = verify_token(&token, secret).expect("valid token should verify");
+        assert_eq!(decoded.sub, "user-123");
+    }
+
+    #[test]
+    fn verify_token_rejects_an_expired_token() {
+        let secret = b"test-secret";
+        let claims = Claims {
+            sub: "user-123".to_string(),
+            exp: unix_timestamp(-3600),
+        };
+        let token = sign(&claims, secret);
+
+        let result = verify_token(&token, secret);
+        assert!(matches!(result, Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn verify_token_rejects_a_wrong_signature() {
+        let claims = Claims {
+            sub: "user-123".to_string(),
+            exp: unix_timestamp(3600),
+        };
+        let token = sign(&claims, b"correct-secret");
+
+        let result = verify_token(&token, b"wrong-secret");
+        assert!(matches!(result, Err(AppError::Unauthorized)));
+    }
+
+    #[test]
+    fn verify_token_rejects_garbage_input() {
+        let result = verify_token("not-a-jwt", b"test-secret");
+        assert!(matches!(result, Err(AppError::Unauthorized)));
+    }
+}
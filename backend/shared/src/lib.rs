@@ -3,11 +3,29 @@
 //! Contains common types, utilities, and business logic used across
 //! multiple Lambda functions.
 
+use lambda_http::http::response::Builder;
+use lambda_http::{Body, Error, Response};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use utoipa::ToSchema;
+
+pub mod auth;
+pub mod csrf;
+
+/// Applies the CORS headers shared by every response-building site in the API
+pub fn cors_headers(builder: Builder) -> Builder {
+    builder
+        .header("access-control-allow-origin", "*")
+        .header("access-control-allow-methods", "GET, POST, PUT, DELETE, OPTIONS")
+        .header(
+            "access-control-allow-headers",
+            "Content-Type, Authorization, X-CSRF-Token",
+        )
+}
 
 /// API response wrapper for consistent responses
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[aliases(ApiResponseHealth = ApiResponse<HealthResponse>)]
 pub struct ApiResponse<T> {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -37,7 +55,7 @@ impl<T> ApiResponse<T> {
 }
 
 /// Application-level errors
-#[derive(Debug, Error)]
+#[derive(Debug, Error, ToSchema)]
 pub enum AppError {
     #[error("Not found: {0}")]
     NotFound(String),
@@ -62,10 +80,19 @@ impl AppError {
             AppError::Unauthorized => 401,
         }
     }
+
+    /// Render this error as the JSON error response sent to the client, at its status code
+    pub fn into_response(&self) -> Result<Response<Body>, Error> {
+        let response: ApiResponse<()> = ApiResponse::error(self.to_string());
+        let json = serde_json::to_string(&response)?;
+        Ok(cors_headers(Response::builder().status(self.status_code()))
+            .header("content-type", "application/json")
+            .body(Body::from(json))?)
+    }
 }
 
 /// Health check response
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub version: String,